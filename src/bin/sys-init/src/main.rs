@@ -1,50 +1,321 @@
 mod xor_server;
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::Duration;
+use std::time::Instant;
+
 use moto_runtime::moturus_log;
 
 // use moto_sys::caps::{CAP_IO_MANAGER, CAP_LOG, CAP_SHARE, CAP_SPAWN};
 use moto_sys::syscalls::*;
 
+/// The env var a supervised service reads to find its one-shot readiness
+/// channel: it connects to this URL and sends any request once it is
+/// listening, instead of sys-init guessing how long startup takes.
+const MOTURUS_READY_URL_ENV_KEY: &str = "MOTURUS_READY_URL";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RestartPolicy {
+    Always,
+    Never,
+    OnFailure,
+}
+
+impl RestartPolicy {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            "on-failure" => Ok(Self::OnFailure),
+            _ => Err(format!("unknown restart policy '{}'", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ServiceSpec {
+    name: String,
+    cmd: String,
+    after: Vec<String>,
+    restart: RestartPolicy,
+    essential: bool,
+    // Opt-in: only a `cmd` that has actually been updated to connect to
+    // MOTURUS_READY_URL and send a request should set `ready_check=true` in
+    // its `service:` line. Everything else is assumed ready as soon as it's
+    // spawned, same as before the readiness channel existed -- until every
+    // binary sys-init.cfg can name implements the handshake, requiring it
+    // unconditionally means start_service times out and panics 5s into boot.
+    ready_check: bool,
+}
+
 #[derive(Debug)]
 struct Config {
-    pub tty: String,
-    pub log: Option<String>,
+    services: Vec<ServiceSpec>,
 }
 
 fn process_config() -> Result<Config, String> {
     let cfg_data = std::fs::read_to_string("/sys/cfg/sys-init.cfg")
         .expect("Error loading /sys/cfg/sys-init.cfg");
 
-    let mut tty = None;
-    let mut log = None;
-
+    let mut services = vec![];
     let mut curr_line = 0_u32;
+
     for line in cfg_data.lines() {
         curr_line += 1;
 
-        if line.trim().is_empty() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
             continue;
         }
 
-        if let Some(file) = line.trim().strip_prefix("tty:") {
-            tty = Some(file.to_owned());
-        } else if let Some(file) = line.trim().strip_prefix("log:") {
-            log = Some(file.to_owned());
-        } else {
+        let Some(fields) = line.strip_prefix("service:") else {
             return Err(format!("'/sys/cfg/sys-init.cfg': bad line {}", curr_line));
+        };
+
+        let mut name = None;
+        let mut cmd = None;
+        let mut after = vec![];
+        let mut restart = RestartPolicy::Never;
+        let mut essential = false;
+        let mut ready_check = false;
+
+        for field in fields.split_whitespace() {
+            let Some((key, val)) = field.split_once('=') else {
+                return Err(format!(
+                    "'/sys/cfg/sys-init.cfg': bad field '{}' on line {}",
+                    field, curr_line
+                ));
+            };
+
+            match key {
+                "name" => name = Some(val.to_owned()),
+                "cmd" => cmd = Some(val.to_owned()),
+                "after" => after = val.split(',').map(str::to_owned).collect(),
+                "restart" => restart = RestartPolicy::parse(val)?,
+                "essential" => essential = val == "true",
+                "ready_check" => ready_check = val == "true",
+                _ => {
+                    return Err(format!(
+                        "'/sys/cfg/sys-init.cfg': unknown key '{}' on line {}",
+                        key, curr_line
+                    ))
+                }
+            }
+        }
+
+        let name = name.ok_or_else(|| format!("service on line {} is missing 'name'", curr_line))?;
+        let cmd = cmd.ok_or_else(|| format!("service on line {} is missing 'cmd'", curr_line))?;
+
+        services.push(ServiceSpec {
+            name,
+            cmd,
+            after,
+            restart,
+            essential,
+            ready_check,
+        });
+    }
+
+    if services.is_empty() {
+        return Err("'/sys/cfg/sys-init.cfg' must list at least one 'service:' line".to_owned());
+    }
+
+    Ok(Config { services })
+}
+
+/// Orders services so that every `after:` dependency starts before the
+/// service that names it. Panics on an unknown dependency or a cycle, same
+/// as a bad line in the config: both mean sys-init.cfg itself is broken.
+fn start_order(specs: &[ServiceSpec]) -> Vec<String> {
+    fn visit(
+        name: &str,
+        required_by: Option<&str>,
+        specs: &[ServiceSpec],
+        done: &mut HashSet<String>,
+        in_progress: &mut HashSet<String>,
+        order: &mut Vec<String>,
+    ) {
+        if done.contains(name) {
+            return;
+        }
+        // `in_progress` is the current recursion stack, separate from
+        // `done`: revisiting a name already on the stack means its `after:`
+        // chain loops back on itself, which `done` alone can't detect.
+        if !in_progress.insert(name.to_owned()) {
+            panic!(
+                "sys-init.cfg: cyclic 'after:' dependency involving '{}'",
+                name
+            );
+        }
+
+        let spec = specs.iter().find(|s| s.name == name).unwrap_or_else(|| match required_by {
+            Some(parent) => panic!(
+                "sys-init.cfg: '{}' depends on unknown service '{}'",
+                parent, name
+            ),
+            None => panic!("sys-init.cfg: unknown service '{}'", name),
+        });
+        for dep in &spec.after {
+            visit(dep, Some(name), specs, done, in_progress, order);
+        }
+
+        in_progress.remove(name);
+        done.insert(name.to_owned());
+        order.push(name.to_owned());
+    }
+
+    let mut done = HashSet::new();
+    let mut in_progress = HashSet::new();
+    let mut order = vec![];
+    for spec in specs {
+        visit(&spec.name, None, specs, &mut done, &mut in_progress, &mut order);
+    }
+    order
+}
+
+/// Blocks until a one-shot readiness signal arrives on `server`, i.e. the
+/// child has connected and sent its first request. Replaces the old fixed
+/// 1ms-sleep-loop-for-5-seconds with an actual wait on the channel.
+///
+/// Only called for services with `ready_check=true` in `sys-init.cfg`: the
+/// child side of this handshake (connecting to `MOTURUS_READY_URL` and
+/// sending a request once listening) is not part of this tree snapshot, so
+/// `ready_check` defaults to `false` and a not-yet-updated `cmd` is assumed
+/// ready as soon as it's spawned, same as before this handshake existed.
+fn wait_ready(server: &mut moto_ipc::sync::LocalServer, timeout: Duration) -> Result<(), String> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if Instant::now() >= deadline {
+            return Err("timed out waiting for the readiness signal".to_owned());
+        }
+
+        match server.wait(moto_sys::SysHandle::NONE, &[]) {
+            Ok(wakers) => {
+                for waker in &wakers {
+                    let Some(conn) = server.get_connection(*waker) else {
+                        continue;
+                    };
+                    if conn.have_req() {
+                        let _ = conn.finish_rpc();
+                        return Ok(());
+                    }
+                }
+            }
+            Err(wakers) => assert_eq!(wakers.len(), 0),
+        }
+    }
+}
+
+struct Supervisor {
+    specs: Vec<ServiceSpec>,
+    running: HashMap<String, std::process::Child>,
+}
+
+impl Supervisor {
+    fn new(config: Config) -> Self {
+        Self {
+            specs: config.services,
+            running: HashMap::new(),
         }
     }
 
-    if tty.is_none() {
-        return Err("'/sys/cfg/sys-init.cfg' must contain 'tty:<filename>' line".to_owned());
+    fn spec(&self, name: &str) -> ServiceSpec {
+        self.specs
+            .iter()
+            .find(|s| s.name == name)
+            .cloned()
+            .unwrap()
     }
 
-    let config = Config {
-        tty: tty.unwrap(),
-        log,
-    };
+    fn start_service(&mut self, name: &str) {
+        let spec = self.spec(name);
 
-    Ok(config)
+        if !spec.ready_check {
+            let child = std::process::Command::new(spec.cmd.as_str())
+                .env(moto_sys::caps::MOTURUS_CAPS_ENV_KEY, "0xffffffffffffffff")
+                .spawn()
+                .unwrap_or_else(|err| panic!("sys-init: error spawning '{}': {:?}", spec.cmd, err));
+
+            self.running.insert(spec.name.clone(), child);
+            return;
+        }
+
+        let ready_url = format!("sys-init-ready:{}", spec.name);
+        let mut ready_server = moto_ipc::sync::LocalServer::new(
+            ready_url.as_str(),
+            moto_ipc::sync::ChannelSize::Small,
+            1,
+            1,
+        )
+        .unwrap_or_else(|err| {
+            panic!(
+                "sys-init: failed to open readiness channel for '{}': {:?}",
+                spec.name, err
+            )
+        });
+
+        let child = std::process::Command::new(spec.cmd.as_str())
+            .env(moto_sys::caps::MOTURUS_CAPS_ENV_KEY, "0xffffffffffffffff")
+            .env(MOTURUS_READY_URL_ENV_KEY, &ready_url)
+            .spawn()
+            .unwrap_or_else(|err| panic!("sys-init: error spawning '{}': {:?}", spec.cmd, err));
+
+        wait_ready(&mut ready_server, Duration::from_secs(5)).unwrap_or_else(|err| {
+            panic!("sys-init: service '{}' never became ready: {}", spec.name, err)
+        });
+
+        self.running.insert(spec.name.clone(), child);
+    }
+
+    fn start_all(&mut self) {
+        for name in start_order(&self.specs) {
+            self.start_service(&name);
+        }
+    }
+
+    /// Watches running services, restarting or shutting the system down
+    /// per each one's policy, until an essential service exits.
+    fn supervise(&mut self) -> ! {
+        loop {
+            let exited: Vec<_> = self
+                .running
+                .iter_mut()
+                .filter_map(|(name, child)| match child.try_wait() {
+                    Ok(Some(status)) => Some((name.clone(), status)),
+                    _ => None,
+                })
+                .collect();
+
+            for (name, status) in exited {
+                self.running.remove(&name);
+                let spec = self.spec(&name);
+
+                moturus_log!("sys-init: service '{}' exited: {:?}.", name, status);
+
+                if spec.essential {
+                    moturus_log!(
+                        "sys-init: essential service '{}' exited; shutting down.",
+                        name
+                    );
+                    std::process::exit(if status.success() { 0 } else { 1 });
+                }
+
+                let should_restart = match spec.restart {
+                    RestartPolicy::Always => true,
+                    RestartPolicy::Never => false,
+                    RestartPolicy::OnFailure => !status.success(),
+                };
+
+                if should_restart {
+                    self.start_service(&name);
+                }
+            }
+
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
 }
 
 fn main() {
@@ -59,36 +330,19 @@ fn main() {
     }
 
     let config = config.unwrap();
+    let mut supervisor = Supervisor::new(config);
+    supervisor.start_all();
 
-    if let Some(log) = &config.log {
-        std::process::Command::new(log.as_str())
-            .spawn()
-            .expect(format!("Error spawning {}", log).as_str());
-
-        // The logserver has just started. It needs time to start
-        // listening, so we need to retry a few times.
-        let log_start = std::time::Instant::now();
-        loop {
-            std::thread::sleep(std::time::Duration::from_millis(1));
-            if log_start.elapsed().as_secs() > 5 {
-                SysMem::log("sys-init: failed to initialize logging").unwrap();
-                std::process::exit(1);
-            }
-            if moto_log::init("sys-init").is_ok() {
-                break;
-            }
-        }
+    // The log service, if configured, has been started by now; it has also
+    // signalled that it is listening only if its line sets ready_check=true.
+    // There is no retry loop: if moto_log::init fails, sys-init panics.
+    if supervisor.running.contains_key("log") {
+        moto_log::init("sys-init").expect("sys-init: failed to attach to the log service");
         log::set_max_level(log::LevelFilter::Info);
     }
 
     // While we are in dev/testing mode, run the xor server/service.
     xor_server::start();
 
-    let mut tty = std::process::Command::new(config.tty.as_str())
-        .env(moto_sys::caps::MOTURUS_CAPS_ENV_KEY, "0xffffffffffffffff")
-        .spawn()
-        .unwrap();
-    tty.wait().unwrap();
-
-    moturus_log!("tty stopped. Shutting down.");
+    supervisor.supervise();
 }
@@ -3,16 +3,17 @@
 #![allow(unused)]
 
 mod load;
+mod posix;
 mod rt_alloc;
 mod rt_fs;
 mod rt_futex;
 mod rt_thread;
 mod rt_time;
 mod rt_tls;
+mod scheme;
 
 #[macro_use]
 mod util {
-    pub mod fd;
     #[macro_use]
     pub mod logging;
     pub mod mutex;
@@ -36,6 +37,8 @@ pub extern "C" fn _rt_entry(version: u64) {
     let self_addr = _rt_entry as *const () as usize as u64;
     assert_eq!(vtable.vdso_entry.load(Ordering::Acquire), self_addr);
 
+    scheme::register_builtin_schemes();
+
     vtable.load_vdso.store(
         load::load_vdso as *const () as usize as u64,
         Ordering::Relaxed,
@@ -220,6 +223,36 @@ pub extern "C" fn _rt_entry(version: u64) {
         .fs_chdir
         .store(rt_fs::chdir as *const () as usize as u64, Ordering::Relaxed);
 
+    // POSIX fd: vectored I/O.
+    vtable.posix_readv.store(
+        posix::posix_readv as *const () as usize as u64,
+        Ordering::Relaxed,
+    );
+    vtable.posix_writev.store(
+        posix::posix_writev as *const () as usize as u64,
+        Ordering::Relaxed,
+    );
+
+    // POSIX fd: zero-copy lend/revoke.
+    vtable.posix_lend.store(
+        posix::posix_lend as *const () as usize as u64,
+        Ordering::Relaxed,
+    );
+    vtable.posix_revoke_lend.store(
+        posix::posix_revoke_lend as *const () as usize as u64,
+        Ordering::Relaxed,
+    );
+
+    // POSIX fd: epoll-style readiness polling.
+    vtable.posix_poll_new.store(
+        posix::poll::posix_poll_new as *const () as usize as u64,
+        Ordering::Relaxed,
+    );
+    vtable.posix_poll_wait.store(
+        posix::poll::posix_poll_wait as *const () as usize as u64,
+        Ordering::Relaxed,
+    );
+
     // The final fence.
     core::sync::atomic::fence(core::sync::atomic::Ordering::Release);
 }
@@ -9,6 +9,7 @@ use core::any::Any;
 
 use super::spin::Mutex;
 use crate::stdio::Stdio;
+use alloc::collections::BTreeMap;
 use alloc::collections::VecDeque;
 use alloc::sync::Arc;
 use alloc::vec::Vec;
@@ -18,6 +19,7 @@ use moto_rt::ErrorCode;
 use moto_rt::RtFd;
 use moto_rt::E_BAD_HANDLE;
 use moto_rt::E_INVALID_ARGUMENT;
+use moto_rt::E_NOT_FOUND;
 use moto_rt::E_OK;
 
 pub trait PosixFile: Any + Send + Sync {
@@ -33,20 +35,214 @@ pub trait PosixFile: Any + Send + Sync {
     fn close(&self) -> Result<(), ErrorCode> {
         Err(E_BAD_HANDLE)
     }
-    fn poll_add(&self, poll_fd: RtFd, token: Token, interests: Interests) -> Result<(), ErrorCode> {
-        todo!()
-        // Err(E_INVALID_ARGUMENT)
+    // `rt_fd` below is the caller's own descriptor: a source registers/deregisters
+    // itself under that identity so that `poll::purge_source()` can find it again
+    // when the source is closed. The default impls are generic and work for any
+    // `PosixFile` (socket, pipe, ...) without per-type overrides.
+    fn poll_add(&self, rt_fd: RtFd, poll_fd: RtFd, token: Token, interests: Interests) -> Result<(), ErrorCode> {
+        poll::register(rt_fd, poll_fd, token, interests)
     }
-    fn poll_set(&self, poll_fd: RtFd, token: Token, interests: Interests) -> Result<(), ErrorCode> {
-        todo!()
-        // Err(E_INVALID_ARGUMENT)
+    fn poll_set(&self, rt_fd: RtFd, poll_fd: RtFd, token: Token, interests: Interests) -> Result<(), ErrorCode> {
+        poll::update(poll_fd, token, interests)
     }
-    fn poll_del(&self, poll_fd: RtFd) -> Result<(), ErrorCode> {
-        todo!()
-        // Err(E_INVALID_ARGUMENT)
+    fn poll_del(&self, rt_fd: RtFd, poll_fd: RtFd) -> Result<(), ErrorCode> {
+        poll::unregister(rt_fd, poll_fd)
+    }
+
+    /// Reports which of this source's interests are satisfied *right now*.
+    /// `poll_wait()` calls this to decide whether to keep re-reporting a
+    /// level-triggered registration; types that don't override it are
+    /// conservatively treated as not ready (rather than re-reporting a
+    /// possibly stale event forever).
+    fn current_readiness(&self) -> Interests {
+        Interests::EMPTY
+    }
+
+    /// Scatter/gather read. The default loops over `read()`, stopping at the
+    /// first short read or the first buffer that returns 0 bytes; types that
+    /// can fill an iovec in one syscall (sockets, pipes) should override this.
+    fn readv(&self, bufs: &mut [IoSliceMut]) -> Result<usize, ErrorCode> {
+        let mut total = 0;
+        for buf in bufs.iter_mut() {
+            let slice = buf.as_mut_slice();
+            if slice.is_empty() {
+                continue;
+            }
+            match self.read(slice) {
+                Ok(0) => break,
+                Ok(n) => {
+                    total += n;
+                    if n < slice.len() {
+                        break;
+                    }
+                }
+                Err(err) if total == 0 => return Err(err),
+                Err(_) => break,
+            }
+        }
+        Ok(total)
+    }
+
+    /// Scatter/gather write. See `readv` for the default strategy.
+    fn writev(&self, bufs: &[IoSlice]) -> Result<usize, ErrorCode> {
+        let mut total = 0;
+        for buf in bufs.iter() {
+            let slice = buf.as_slice();
+            if slice.is_empty() {
+                continue;
+            }
+            match self.write(slice) {
+                Ok(0) => break,
+                Ok(n) => {
+                    total += n;
+                    if n < slice.len() {
+                        break;
+                    }
+                }
+                Err(err) if total == 0 => return Err(err),
+                Err(_) => break,
+            }
+        }
+        Ok(total)
+    }
+
+    /// Zero-copy path for large transfers: hands `range` of this file to the
+    /// backing service (fs/net server, reached over `moto_ipc::sync`) as a
+    /// shared mapping instead of copying through `read`/`write`. Types that
+    /// cannot support this (in-memory pipes, scheme adapters, ...) keep the
+    /// default, which simply reports it as unsupported.
+    fn lend(&self, _range: core::ops::Range<u64>) -> Result<LentBuffer, ErrorCode> {
+        Err(E_BAD_HANDLE)
+    }
+
+    /// Revokes a buffer previously handed out by `lend()`. Called from
+    /// `posix_close()` so a lent mapping never outlives the fd it came from.
+    fn revoke_lend(&self, _buffer: &LentBuffer) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// A single destination buffer of a scatter/gather `readv`.
+#[repr(C)]
+pub struct IoSliceMut {
+    pub ptr: *mut u8,
+    pub len: usize,
+}
+
+impl IoSliceMut {
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+/// A single source buffer of a scatter/gather `writev`.
+#[repr(C)]
+pub struct IoSlice {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl IoSlice {
+    fn as_slice(&self) -> &[u8] {
+        unsafe { core::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+/// A page-aligned region of this file mapped directly into the caller's
+/// address space by `PosixFile::lend()`, rather than copied via `read`.
+#[derive(Clone, Copy)]
+pub struct LentBuffer {
+    pub addr: u64,
+    pub len: u64,
+}
+
+struct LendRegistry {
+    // rt_fd -> the buffers `lend()` has handed out for it and that haven't
+    // been returned yet, so a close mid-transfer has something to revoke.
+    by_fd: Mutex<BTreeMap<RtFd, Vec<LentBuffer>>>,
+}
+
+impl LendRegistry {
+    const fn new() -> Self {
+        Self {
+            by_fd: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+static LEND_REGISTRY: LendRegistry = LendRegistry::new();
+
+/// Lends `range` of the file at `rt_fd`, tracking the resulting buffer so
+/// `posix_close()` can revoke it if the fd is closed mid-transfer.
+pub fn lend(rt_fd: RtFd, range: core::ops::Range<u64>) -> Result<LentBuffer, ErrorCode> {
+    let file = get_file(rt_fd).ok_or(E_BAD_HANDLE)?;
+    let buffer = file.lend(range)?;
+
+    LEND_REGISTRY
+        .by_fd
+        .lock()
+        .entry(rt_fd)
+        .or_insert_with(Vec::new)
+        .push(buffer);
+
+    Ok(buffer)
+}
+
+/// Revokes every buffer still outstanding for `rt_fd`. Called from
+/// `posix_close()`, before the file itself is dropped, so a lent mapping
+/// never outlives the fd it came from.
+fn revoke_all_lends(rt_fd: RtFd, file: &Arc<dyn PosixFile>) {
+    let Some(buffers) = LEND_REGISTRY.by_fd.lock().remove(&rt_fd) else {
+        return;
+    };
+
+    for buffer in &buffers {
+        file.revoke_lend(buffer);
+    }
+}
+
+/// Drops the bookkeeping for a single buffer a caller is returning through
+/// `posix_revoke_lend()`, as opposed to `revoke_all_lends()` dropping every
+/// buffer still outstanding when the fd itself closes.
+fn forget_lend(rt_fd: RtFd, buffer: &LentBuffer) {
+    let mut by_fd = LEND_REGISTRY.by_fd.lock();
+    let Some(buffers) = by_fd.get_mut(&rt_fd) else {
+        return;
+    };
+    buffers.retain(|b| b.addr != buffer.addr || b.len != buffer.len);
+    if buffers.is_empty() {
+        by_fd.remove(&rt_fd);
+    }
+}
+
+/// `PosixFile::lend()`, as wired into `_rt_entry`'s `posix_lend` vtable slot.
+pub extern "C" fn posix_lend(rt_fd: RtFd, start: u64, end: u64, out: *mut LentBuffer) -> ErrorCode {
+    match lend(rt_fd, start..end) {
+        Ok(buffer) => {
+            unsafe {
+                *out = buffer;
+            }
+            E_OK
+        }
+        Err(err) => err,
     }
 }
 
+/// `PosixFile::revoke_lend()`, as wired into `_rt_entry`'s `posix_revoke_lend`
+/// vtable slot: returns a buffer a caller is done with before the fd closes.
+pub extern "C" fn posix_revoke_lend(rt_fd: RtFd, buffer: *const LentBuffer) -> ErrorCode {
+    let Some(file) = get_file(rt_fd) else {
+        return E_BAD_HANDLE;
+    };
+    let buffer = unsafe { &*buffer };
+
+    file.revoke_lend(buffer);
+    forget_lend(rt_fd, buffer);
+    E_OK
+}
+
 pub extern "C" fn posix_read(rt_fd: i32, buf: *mut u8, buf_sz: usize) -> i64 {
     let posix_file = if let Some(fd) = get_file(rt_fd) {
         fd
@@ -75,6 +271,34 @@ pub extern "C" fn posix_write(rt_fd: i32, buf: *const u8, buf_sz: usize) -> i64
     }
 }
 
+pub extern "C" fn posix_readv(rt_fd: i32, iov: *mut IoSliceMut, iov_cnt: usize) -> i64 {
+    let posix_file = if let Some(fd) = get_file(rt_fd) {
+        fd
+    } else {
+        return -(E_BAD_HANDLE as i64);
+    };
+
+    let bufs = unsafe { core::slice::from_raw_parts_mut(iov, iov_cnt) };
+    match posix_file.readv(bufs) {
+        Ok(sz) => sz as i64,
+        Err(err) => -(err as i64),
+    }
+}
+
+pub extern "C" fn posix_writev(rt_fd: i32, iov: *const IoSlice, iov_cnt: usize) -> i64 {
+    let posix_file = if let Some(fd) = get_file(rt_fd) {
+        fd
+    } else {
+        return -(E_BAD_HANDLE as i64);
+    };
+
+    let bufs = unsafe { core::slice::from_raw_parts(iov, iov_cnt) };
+    match posix_file.writev(bufs) {
+        Ok(sz) => sz as i64,
+        Err(err) => -(err as i64),
+    }
+}
+
 pub extern "C" fn posix_flush(rt_fd: i32) -> ErrorCode {
     let posix_file = if let Some(fd) = get_file(rt_fd) {
         fd
@@ -89,13 +313,31 @@ pub extern "C" fn posix_flush(rt_fd: i32) -> ErrorCode {
 }
 
 pub extern "C" fn posix_close(rt_fd: i32) -> ErrorCode {
-    let posix_file = if let Some(fd) = pop_file(rt_fd) {
+    // `take_file` removes the descriptor but keeps `rt_fd`'s number out of
+    // the freelist until `release_file` below: purge_source()/revoke_all_lends()
+    // and a poll object's own close() all key off `rt_fd` itself, and if the
+    // number were reusable the moment
+    // the descriptor slot is cleared, a concurrent new_file() could be handed
+    // this exact number and have its brand-new registrations/lent buffers
+    // wiped out by this call's delayed cleanup pass.
+    let posix_file = if let Some(fd) = take_file(rt_fd) {
         fd
     } else {
         return E_BAD_HANDLE;
     };
 
-    match posix_file.close() {
+    // A closed fd must disappear from every poll set that still references it,
+    // regardless of its concrete type.
+    poll::purge_source(rt_fd);
+
+    // Likewise, any buffer still lent out from this fd must be revoked
+    // rather than left mapped past the fd's lifetime.
+    revoke_all_lends(rt_fd, &posix_file);
+
+    let result = posix_file.close();
+    release_file(rt_fd);
+
+    match result {
         Ok(()) => E_OK,
         Err(err) => err,
     }
@@ -156,20 +398,26 @@ impl Descriptors {
     }
 
     fn pop(&self, fd: RtFd) -> Option<Arc<dyn PosixFile>> {
-        let val = {
-            let mut descriptors = self.descriptors.lock();
-            if let Some(entry) = descriptors.get_mut(fd as usize) {
-                let mut val: Arc<dyn PosixFile> = Arc::new(Placeholder);
-                core::mem::swap(&mut val, entry);
-                Some(val)
-            } else {
-                return None;
-            }
-        };
-        if val.is_some() {
-            self.freelist.lock().push(fd);
-        }
-        val
+        let val = self.take(fd)?;
+        self.release(fd);
+        Some(val)
+    }
+
+    /// Removes the descriptor at `fd` without returning its number to the
+    /// freelist, so a caller that still needs `fd`'s identity (e.g. to purge
+    /// stale poll registrations keyed by it) can finish before the number
+    /// becomes reusable. Pair with `release()`.
+    fn take(&self, fd: RtFd) -> Option<Arc<dyn PosixFile>> {
+        let mut descriptors = self.descriptors.lock();
+        let entry = descriptors.get_mut(fd as usize)?;
+        let mut val: Arc<dyn PosixFile> = Arc::new(Placeholder);
+        core::mem::swap(&mut val, entry);
+        Some(val)
+    }
+
+    /// Returns `fd` to the freelist. Only valid after a matching `take()`.
+    fn release(&self, fd: RtFd) {
+        self.freelist.lock().push(fd);
     }
 
     fn get_free_fd(&self) -> RtFd {
@@ -221,3 +469,374 @@ pub fn get_file(fd: RtFd) -> Option<Arc<dyn PosixFile>> {
 pub fn pop_file(fd: RtFd) -> Option<Arc<dyn PosixFile>> {
     DESCRIPTORS.pop(fd)
 }
+
+/// See `Descriptors::take()`. Pair with `release_file()`.
+fn take_file(fd: RtFd) -> Option<Arc<dyn PosixFile>> {
+    DESCRIPTORS.take(fd)
+}
+
+/// See `Descriptors::release()`. Only valid after a matching `take_file()`.
+fn release_file(fd: RtFd) {
+    DESCRIPTORS.release(fd)
+}
+
+/// Wraps a `SysHandle` received from a peer (via the `moto_ipc::sync`
+/// `recv_handle()` companion API) in a local `RtFd`, so a server can hand a
+/// client a ready-made socket or file instead of round-tripping every
+/// read/write through IPC.
+struct HandleFile {
+    handle: moto_sys::SysHandle,
+}
+
+impl PosixFile for HandleFile {
+    fn read(&self, buf: &mut [u8]) -> Result<usize, ErrorCode> {
+        moto_sys::syscalls::SysObj::read(self.handle, buf).map_err(Into::into)
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize, ErrorCode> {
+        moto_sys::syscalls::SysObj::write(self.handle, buf).map_err(Into::into)
+    }
+
+    fn close(&self) -> Result<(), ErrorCode> {
+        moto_sys::syscalls::SysObj::put(self.handle).map_err(Into::into)
+    }
+}
+
+pub fn adopt_handle(handle: moto_sys::SysHandle) -> RtFd {
+    push_file(Arc::new(HandleFile { handle }))
+}
+
+/// The epoll-style readiness-notification primitive.
+///
+/// A `PollFd` is itself just another [`PosixFile`], created via [`new_file`]
+/// like any other descriptor. Sources (sockets, pipes, ...) register against
+/// it by `poll_fd`, and report readiness by calling [`notify`].
+pub mod poll {
+    use super::*;
+    use core::sync::atomic::AtomicU32;
+    use core::sync::atomic::Ordering;
+
+    struct Registration {
+        rt_fd: RtFd,
+        interests: Interests,
+    }
+
+    pub struct PollFd {
+        // This poll object's own rt_fd, so `close()` can find and remove its
+        // `(poll_fd, token)` entries from `SOURCE_INDEX` without
+        // depending on the caller to pass it back in.
+        self_fd: RtFd,
+        // Token -> the source registered under it. Only looked up by token;
+        // poll_wait() delivers events in `ready`'s FIFO order, not in the
+        // order of this map.
+        registrations: Mutex<BTreeMap<Token, Registration>>,
+        ready: Mutex<VecDeque<(Token, Interests)>>,
+        // Bumped on every push to `ready`; futex_wait() validates against it
+        // atomically, so a notify() that races a waiter about to block can
+        // never be missed.
+        futex: AtomicU32,
+    }
+
+    impl PollFd {
+        fn new(self_fd: RtFd) -> Arc<Self> {
+            Arc::new(Self {
+                self_fd,
+                registrations: Mutex::new(BTreeMap::new()),
+                ready: Mutex::new(VecDeque::new()),
+                futex: AtomicU32::new(0),
+            })
+        }
+
+        fn wake(&self) {
+            self.futex.fetch_add(1, Ordering::Release);
+            crate::rt_futex::futex_wake_all(&self.futex);
+        }
+    }
+
+    impl PosixFile for PollFd {
+        fn close(&self) -> Result<(), ErrorCode> {
+            // Every source still registered here has a (self_fd, token)
+            // entry in SOURCE_INDEX; left behind, Descriptors' LIFO freelist
+            // could hand this fd number to an unrelated new file, and that
+            // source's eventual close() would have purge_source() find the
+            // new object here and spuriously mutate its registrations.
+            let sources: Vec<RtFd> = self
+                .registrations
+                .lock()
+                .values()
+                .map(|reg| reg.rt_fd)
+                .collect();
+
+            let mut by_source = SOURCE_INDEX.by_source.lock();
+            for rt_fd in sources {
+                if let Some(entries) = by_source.get_mut(&rt_fd) {
+                    entries.retain(|(pfd, _)| *pfd != self.self_fd);
+                    if entries.is_empty() {
+                        by_source.remove(&rt_fd);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// Creates a new poll object and returns its `RtFd`, as used by the
+    /// `epoll_create`-equivalent syscall shim.
+    pub extern "C" fn posix_poll_new() -> RtFd {
+        new_file(PollFd::new)
+    }
+
+    // source rt_fd -> the (poll_fd, token) pairs it is registered under, so
+    // that closing the source can find and remove every stale registration.
+    struct SourceIndex {
+        by_source: Mutex<BTreeMap<RtFd, Vec<(RtFd, Token)>>>,
+    }
+
+    impl SourceIndex {
+        const fn new() -> Self {
+            Self {
+                by_source: Mutex::new(BTreeMap::new()),
+            }
+        }
+    }
+
+    static SOURCE_INDEX: SourceIndex = SourceIndex::new();
+
+    fn get_poll(poll_fd: RtFd) -> Result<Arc<dyn PosixFile>, ErrorCode> {
+        get_file(poll_fd).ok_or(E_BAD_HANDLE)
+    }
+
+    pub fn register(rt_fd: RtFd, poll_fd: RtFd, token: Token, interests: Interests) -> Result<(), ErrorCode> {
+        let poll_file = get_poll(poll_fd)?;
+        let poll = poll_file
+            .as_any()
+            .downcast_ref::<PollFd>()
+            .ok_or(E_INVALID_ARGUMENT)?;
+
+        {
+            let mut regs = poll.registrations.lock();
+            if regs.contains_key(&token) {
+                return Err(E_INVALID_ARGUMENT);
+            }
+            regs.insert(token, Registration { rt_fd, interests });
+        }
+
+        SOURCE_INDEX
+            .by_source
+            .lock()
+            .entry(rt_fd)
+            .or_insert_with(Vec::new)
+            .push((poll_fd, token));
+
+        Ok(())
+    }
+
+    pub fn update(poll_fd: RtFd, token: Token, interests: Interests) -> Result<(), ErrorCode> {
+        let poll_file = get_poll(poll_fd)?;
+        let poll = poll_file
+            .as_any()
+            .downcast_ref::<PollFd>()
+            .ok_or(E_INVALID_ARGUMENT)?;
+
+        let mut regs = poll.registrations.lock();
+        let reg = regs.get_mut(&token).ok_or(E_NOT_FOUND)?;
+        reg.interests = interests;
+        Ok(())
+    }
+
+    /// Removes every registration `rt_fd` holds in `poll_fd`'s set, along
+    /// with any event(s) already queued for it. The ready-queue purge is
+    /// what keeps this race-free against a concurrent `notify()`: once this
+    /// returns, no stale event for `rt_fd` can be delivered, and its token
+    /// is safe to reuse in a fresh `register()`.
+    pub fn unregister(rt_fd: RtFd, poll_fd: RtFd) -> Result<(), ErrorCode> {
+        let poll_file = get_poll(poll_fd)?;
+        let poll = poll_file
+            .as_any()
+            .downcast_ref::<PollFd>()
+            .ok_or(E_INVALID_ARGUMENT)?;
+
+        remove_registrations(poll, |_token, reg| reg.rt_fd == rt_fd);
+
+        let mut by_source = SOURCE_INDEX.by_source.lock();
+        if let Some(entries) = by_source.get_mut(&rt_fd) {
+            entries.retain(|(pfd, _)| *pfd != poll_fd);
+            if entries.is_empty() {
+                by_source.remove(&rt_fd);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Called from `posix_close()` for every closed fd, regardless of its
+    /// concrete type, so a source never outlives itself in a poll set.
+    pub fn purge_source(rt_fd: RtFd) {
+        let Some(entries) = SOURCE_INDEX.by_source.lock().remove(&rt_fd) else {
+            return;
+        };
+
+        for (poll_fd, token) in entries {
+            if let Some(poll_file) = get_file(poll_fd) {
+                if let Some(poll) = poll_file.as_any().downcast_ref::<PollFd>() {
+                    remove_registrations(poll, |t, _reg| *t == token);
+                }
+            }
+        }
+    }
+
+    /// Removes every registration matching `matches`, then any events
+    /// already queued for the removed tokens. The `registrations` and
+    /// `ready` locks are taken one after the other, not together; a waiter
+    /// that pops such an event in between finds no registration for its
+    /// token and drops it (see `posix_poll_wait()`).
+    fn remove_registrations(poll: &PollFd, matches: impl Fn(&Token, &Registration) -> bool) {
+        let removed: Vec<Token> = {
+            let mut regs = poll.registrations.lock();
+            let mut removed = Vec::new();
+            regs.retain(|token, reg| {
+                if matches(token, reg) {
+                    removed.push(*token);
+                    false
+                } else {
+                    true
+                }
+            });
+            removed
+        };
+
+        if !removed.is_empty() {
+            poll.ready.lock().retain(|(token, _)| !removed.contains(token));
+        }
+    }
+
+    /// A source calls this once its readiness changes. `readiness` is the
+    /// subset of the registered interests that is currently satisfied.
+    pub fn notify(poll_fd: RtFd, token: Token, readiness: Interests) {
+        let Some(poll_file) = get_file(poll_fd) else {
+            return;
+        };
+        let Some(poll) = poll_file.as_any().downcast_ref::<PollFd>() else {
+            return;
+        };
+
+        poll.ready.lock().push_back((token, readiness));
+        poll.wake();
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct PollEvent {
+        pub token: Token,
+        pub readiness: Interests,
+    }
+
+    /// Drains ready events into `events_out` (capacity `max_events`),
+    /// blocking on the poll object's futex until at least one is available
+    /// or `timeout_ms` (negative meaning "forever") elapses. Returns the
+    /// number of events written, or a negative `ErrorCode` on error.
+    pub extern "C" fn posix_poll_wait(
+        poll_fd: RtFd,
+        events_out: *mut PollEvent,
+        max_events: usize,
+        timeout_ms: i64,
+    ) -> i64 {
+        let poll_file = match get_poll(poll_fd) {
+            Ok(f) => f,
+            Err(err) => return -(err as i64),
+        };
+        let poll = match poll_file.as_any().downcast_ref::<PollFd>() {
+            Some(p) => p,
+            None => return -(E_INVALID_ARGUMENT as i64),
+        };
+
+        let events = unsafe { core::slice::from_raw_parts_mut(events_out, max_events) };
+        let deadline = if timeout_ms < 0 {
+            None
+        } else {
+            Some(
+                crate::rt_time::time_instant_now()
+                    + crate::rt_time::nanos_to_ticks((timeout_ms as u64).saturating_mul(1_000_000)),
+            )
+        };
+
+        loop {
+            let expected = poll.futex.load(Ordering::Acquire);
+
+            let mut drained = Vec::new();
+            {
+                let mut ready = poll.ready.lock();
+                while drained.len() < events.len() {
+                    match ready.pop_front() {
+                        Some(event) => drained.push(event),
+                        None => break,
+                    }
+                }
+            }
+
+            if !drained.is_empty() {
+                let mut requeue = Vec::new();
+                let mut n = 0usize;
+
+                {
+                    let mut regs = poll.registrations.lock();
+                    for (token, readiness) in drained {
+                        // The registration may have been removed by a
+                        // concurrent poll_del()/close() between the pop
+                        // above and this lookup; in that case the event no
+                        // longer belongs to anything in this poll set, so
+                        // drop it instead of delivering (or re-queuing) it.
+                        let Some(reg) = regs.get(&token) else {
+                            continue;
+                        };
+
+                        let is_oneshot = reg.interests.is_oneshot();
+                        let is_level_triggered = reg.interests.is_level_triggered();
+                        let source_rt_fd = reg.rt_fd;
+
+                        if is_oneshot {
+                            regs.remove(&token);
+                        }
+
+                        events[n] = PollEvent { token, readiness };
+                        n += 1;
+
+                        if is_level_triggered && !is_oneshot {
+                            requeue.push((token, source_rt_fd));
+                        }
+                    }
+                }
+
+                // Level-triggered: only re-report if the source says it is
+                // still actually ready, rather than blindly re-queuing the
+                // same stale event forever.
+                for (token, source_rt_fd) in requeue {
+                    let Some(source) = get_file(source_rt_fd) else {
+                        continue;
+                    };
+                    let still_ready = source.current_readiness();
+                    if !still_ready.is_empty() {
+                        poll.ready.lock().push_back((token, still_ready));
+                        poll.wake();
+                    }
+                }
+
+                if n > 0 {
+                    return n as i64;
+                }
+                // Every drained event was stale; loop back and wait again.
+            }
+
+            if let Some(dl) = deadline {
+                let now = crate::rt_time::time_instant_now();
+                if now >= dl {
+                    return 0;
+                }
+                let _ = crate::rt_futex::futex_wait(&poll.futex, expected, Some(dl - now));
+            } else {
+                let _ = crate::rt_futex::futex_wait(&poll.futex, expected, None);
+            }
+        }
+    }
+}
@@ -0,0 +1,48 @@
+//! Filesystem vtable entries.
+//!
+//! Only `open()` is implemented in this tree: it is the scheme-registry
+//! integration point called out by the scheme-registry change (see
+//! `crate::scheme`). Everything else this module is expected to back
+//! (`close`, `read`, `write`, `mkdir`, ...) dispatches to the regular fs
+//! server over `moto_ipc::sync` and lives outside this tree snapshot. Opening
+//! an ordinary (non-scheme) path falls into that same gap; `open_local()`
+//! reports it as `E_NOT_IMPLEMENTED` instead of panicking, since `fs_open`
+//! takes that path for every file that isn't behind a registered scheme.
+
+use crate::scheme;
+use moto_rt::ErrorCode;
+use moto_rt::RtFd;
+use moto_rt::E_INVALID_ARGUMENT;
+use moto_rt::E_NOT_IMPLEMENTED;
+
+/// `rt_fs::open()`, as wired into `_rt_entry`'s `fs_open` vtable slot.
+///
+/// Tries the scheme registry first: a path of the form `<prefix>:...`
+/// whose `<prefix>` is registered (e.g. `stats:/tcp`) is opened through
+/// that `Scheme` instead of the local filesystem. Any other path goes to
+/// `open_local()`, which returns `E_NOT_IMPLEMENTED`.
+pub extern "C" fn open(path_ptr: *const u8, path_len: usize, flags: u32) -> i64 {
+    let path = unsafe {
+        let bytes = core::slice::from_raw_parts(path_ptr, path_len);
+        match core::str::from_utf8(bytes) {
+            Ok(s) => s,
+            Err(_) => return -(E_INVALID_ARGUMENT as i64),
+        }
+    };
+
+    if let Some(result) = scheme::try_open(path, flags) {
+        return match result {
+            Ok(rt_fd) => rt_fd as i64,
+            Err(err) => -(err as i64),
+        };
+    }
+
+    open_local(path, flags)
+}
+
+/// The non-scheme fallback. It does not contact the fs server: every
+/// ordinary (non-scheme) path gets `E_NOT_IMPLEMENTED`, returned as an
+/// error rather than a panic of the caller's process.
+fn open_local(_path: &str, _flags: u32) -> i64 {
+    -(E_NOT_IMPLEMENTED as i64)
+}
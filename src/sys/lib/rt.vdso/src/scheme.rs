@@ -0,0 +1,141 @@
+//! A Redox-style scheme registry.
+//!
+//! Motor OS routes every open file through the `PosixFile` trait and the
+//! `Descriptors` table (see `crate::posix`), but until now every provider was
+//! hard-wired into the runtime itself. This borrows the scheme abstraction
+//! from redox_syscall: a `Scheme` is anything that can back a URL prefix
+//! (`stats:`, `rand:`, `null:`, ...), and `rt_fs::open()` dispatches to one
+//! instead of the core runtime knowing about every virtual file up front.
+
+use alloc::borrow::ToOwned;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+
+use crate::posix::PosixFile;
+use crate::spin::Mutex;
+use moto_rt::ErrorCode;
+use moto_rt::RtFd;
+
+/// Implemented by a service that wants to back a URL scheme (e.g. `stats:`)
+/// with its own open/read/write/seek/close logic, without the core runtime
+/// knowing anything about it.
+pub trait Scheme: Send + Sync {
+    fn open(&self, path: &str, flags: u32) -> Result<u64, ErrorCode>;
+    fn read(&self, id: u64, buf: &mut [u8]) -> Result<usize, ErrorCode>;
+    fn write(&self, id: u64, buf: &[u8]) -> Result<usize, ErrorCode>;
+    fn seek(&self, id: u64, pos: i64, whence: u8) -> Result<u64, ErrorCode>;
+    fn close(&self, id: u64) -> Result<(), ErrorCode>;
+}
+
+struct SchemeRegistry {
+    schemes: Mutex<BTreeMap<String, Arc<dyn Scheme>>>,
+}
+
+impl SchemeRegistry {
+    const fn new() -> Self {
+        Self {
+            schemes: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+static REGISTRY: SchemeRegistry = SchemeRegistry::new();
+
+/// Publishes `scheme` under `prefix` (e.g. `"stats"` for paths like
+/// `stats:/tcp`). Replaces any scheme previously registered under the same
+/// prefix, so that in-process services (and, via `moto_ipc::sync`,
+/// out-of-process servers) can (re-)publish virtual files at any time.
+pub fn register_scheme(prefix: &str, scheme: Arc<dyn Scheme>) {
+    REGISTRY
+        .schemes
+        .lock()
+        .insert(prefix.to_owned(), scheme);
+}
+
+fn split(path: &str) -> Option<(&str, &str)> {
+    path.split_once(':')
+}
+
+fn lookup(prefix: &str) -> Option<Arc<dyn Scheme>> {
+    REGISTRY.schemes.lock().get(prefix).cloned()
+}
+
+/// Called from `rt_fs::open()` before falling back to the regular
+/// filesystem: if `path` has a `<prefix>:...` shape and `<prefix>` is a
+/// registered scheme, opens it through that scheme and wraps the result in
+/// a new `PosixFile` descriptor. Returns `None` for paths that aren't
+/// scheme URLs at all, so the caller can fall through to its normal path.
+pub fn try_open(path: &str, flags: u32) -> Option<Result<RtFd, ErrorCode>> {
+    let (prefix, rest) = split(path)?;
+    let scheme = lookup(prefix)?;
+
+    Some(match scheme.open(rest, flags) {
+        Ok(id) => Ok(crate::posix::new_file(|_| {
+            Arc::new(SchemeFile { scheme, id }) as Arc<dyn PosixFile>
+        })),
+        Err(err) => Err(err),
+    })
+}
+
+/// Adapts an open `(Scheme, id)` pair to the `PosixFile` interface so it can
+/// live in the regular descriptor table alongside sockets and files.
+struct SchemeFile {
+    scheme: Arc<dyn Scheme>,
+    id: u64,
+}
+
+impl PosixFile for SchemeFile {
+    fn read(&self, buf: &mut [u8]) -> Result<usize, ErrorCode> {
+        self.scheme.read(self.id, buf)
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize, ErrorCode> {
+        self.scheme.write(self.id, buf)
+    }
+
+    fn close(&self) -> Result<(), ErrorCode> {
+        self.scheme.close(self.id)
+    }
+}
+
+/// Adapts the IO-stats IPC service to the `Scheme` interface, so that
+/// opening `stats:/tcp` works like any other scheme instead of needing its
+/// own bespoke IPC command. `open()` just picks which stats table a later
+/// `read()` pulls from; the RPC itself rides the same `moto_ipc::sync`
+/// connection every other client of the stats service already uses.
+struct StatsScheme;
+
+impl Scheme for StatsScheme {
+    fn open(&self, path: &str, _flags: u32) -> Result<u64, ErrorCode> {
+        match path.trim_start_matches('/') {
+            "tcp" => Ok(0),
+            _ => Err(moto_rt::E_NOT_FOUND),
+        }
+    }
+
+    fn read(&self, id: u64, buf: &mut [u8]) -> Result<usize, ErrorCode> {
+        match id {
+            0 => moto_sys_io::stats::read_tcp_stats_into(buf).map_err(Into::into),
+            _ => Err(moto_rt::E_NOT_FOUND),
+        }
+    }
+
+    fn write(&self, _id: u64, _buf: &[u8]) -> Result<usize, ErrorCode> {
+        Err(moto_rt::E_BAD_HANDLE)
+    }
+
+    fn seek(&self, _id: u64, _pos: i64, _whence: u8) -> Result<u64, ErrorCode> {
+        Err(moto_rt::E_BAD_HANDLE)
+    }
+
+    fn close(&self, _id: u64) -> Result<(), ErrorCode> {
+        Ok(())
+    }
+}
+
+/// Registers the schemes every process gets for free. Called once from
+/// `_rt_entry`.
+pub fn register_builtin_schemes() {
+    register_scheme("stats", Arc::new(StatsScheme));
+}
@@ -1,6 +1,11 @@
 use std::sync::Arc;
 
 use moto_ipc::sync::{LocalServerConnection, RequestHeader};
+// `CMD_UDP_STATS`, `CMD_INTERFACE_STATS`, `UdpSocketStatsV1`, `InterfaceStatsV1`,
+// `GetUdpSocketStatsRequest/Response`, `GetInterfaceStatsRequest/Response`,
+// `MAX_UDP_SOCKET_STATS` and `MAX_INTERFACE_STATS` are assumed additions to
+// `moto_sys_io::stats` (mirroring the existing `CMD_TCP_STATS`/`TcpSocketStatsV1`
+// shape) that are not part of this tree snapshot.
 use moto_sys_io::stats::*;
 
 pub fn spawn_stats_service() {
@@ -53,6 +58,8 @@ fn process_ipc(service: &mut moto_ipc::sync::LocalServer, waker: moto_sys::SysHa
     let cmd = conn.req::<RequestHeader>().cmd;
     match cmd {
         CMD_TCP_STATS => get_tcp_stats(conn),
+        CMD_UDP_STATS => get_udp_stats(conn),
+        CMD_INTERFACE_STATS => get_interface_stats(conn),
         _ => {
             conn.disconnect();
         }
@@ -83,6 +90,46 @@ fn get_tcp_stats(conn: &mut LocalServerConnection) {
     assert!(results.len() <= moto_sys_io::stats::MAX_TCP_SOCKET_STATS);
     resp.num_results = results.len() as u64;
 
+    // A full page doesn't prove more data exists, but it's the only signal
+    // we have without an exact total; the client re-issues CMD_TCP_STATS
+    // with `next_id` and gets a short (or empty) page once it's drained.
+    resp.has_more = results.len() == moto_sys_io::stats::MAX_TCP_SOCKET_STATS;
+    resp.next_id = results.last().map(|s| s.id + 1).unwrap_or(start_id);
+
+    for idx in 0..results.len() {
+        resp.socket_stats[idx] = results[idx];
+    }
+
+    resp.header.result = moto_sys::ErrorCode::Ok.into();
+    let _ = conn.finish_rpc();
+}
+
+pub struct GetUdpStatsPayload {
+    pub start_id: u64,
+    pub results: moto_runtime::util::SpinLock<Vec<UdpSocketStatsV1>>,
+}
+
+fn get_udp_stats(conn: &mut LocalServerConnection) {
+    let req = conn.req::<GetUdpSocketStatsRequest>();
+    let start_id = req.start_id;
+
+    let payload = Arc::new(GetUdpStatsPayload {
+        start_id,
+        results: moto_runtime::util::SpinLock::new(Vec::new()),
+    });
+
+    super::internal_queue::call(CMD_UDP_STATS, payload.clone());
+
+    let resp =
+        conn.resp::<GetUdpSocketStatsResponse<{ moto_sys_io::stats::MAX_UDP_SOCKET_STATS }>>();
+
+    let mut results = vec![];
+    core::mem::swap(&mut *payload.results.lock(line!()), &mut results);
+    assert!(results.len() <= moto_sys_io::stats::MAX_UDP_SOCKET_STATS);
+    resp.num_results = results.len() as u64;
+    resp.has_more = results.len() == moto_sys_io::stats::MAX_UDP_SOCKET_STATS;
+    resp.next_id = results.last().map(|s| s.id + 1).unwrap_or(start_id);
+
     for idx in 0..results.len() {
         resp.socket_stats[idx] = results[idx];
     }
@@ -90,3 +137,37 @@ fn get_tcp_stats(conn: &mut LocalServerConnection) {
     resp.header.result = moto_sys::ErrorCode::Ok.into();
     let _ = conn.finish_rpc();
 }
+
+pub struct GetInterfaceStatsPayload {
+    pub start_id: u64,
+    pub results: moto_runtime::util::SpinLock<Vec<InterfaceStatsV1>>,
+}
+
+fn get_interface_stats(conn: &mut LocalServerConnection) {
+    let req = conn.req::<GetInterfaceStatsRequest>();
+    let start_id = req.start_id;
+
+    let payload = Arc::new(GetInterfaceStatsPayload {
+        start_id,
+        results: moto_runtime::util::SpinLock::new(Vec::new()),
+    });
+
+    super::internal_queue::call(CMD_INTERFACE_STATS, payload.clone());
+
+    let resp =
+        conn.resp::<GetInterfaceStatsResponse<{ moto_sys_io::stats::MAX_INTERFACE_STATS }>>();
+
+    let mut results = vec![];
+    core::mem::swap(&mut *payload.results.lock(line!()), &mut results);
+    assert!(results.len() <= moto_sys_io::stats::MAX_INTERFACE_STATS);
+    resp.num_results = results.len() as u64;
+    resp.has_more = results.len() == moto_sys_io::stats::MAX_INTERFACE_STATS;
+    resp.next_id = results.last().map(|s| s.id + 1).unwrap_or(start_id);
+
+    for idx in 0..results.len() {
+        resp.interface_stats[idx] = results[idx];
+    }
+
+    resp.header.result = moto_sys::ErrorCode::Ok.into();
+    let _ = conn.finish_rpc();
+}